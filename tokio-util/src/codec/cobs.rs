@@ -22,6 +22,10 @@ pub struct CobsCodec {
     // Are we currently discarding the remainder of a bytes which was over
     // the length limit?
     is_discarding: bool,
+
+    // Whether to use the COBS/R (reduced overhead) variant, which drops the final
+    // overhead byte of a frame in the common case.
+    reduced: bool,
 }
 
 impl CobsCodec {
@@ -38,19 +42,95 @@ impl CobsCodec {
             delimiter,
             max_length,
             is_discarding: false,
+            reduced: false,
+        }
+    }
+
+    /// Provide a new codec using the COBS/R (reduced overhead) variant, which saves one
+    /// trailing overhead byte per frame for the common case where the final data byte's
+    /// value is at least as large as its group's length code. A 0 is used as the delimiter.
+    pub fn new_reduced(max_length: usize) -> Self {
+        Self::new_reduced_with_delimiter(0, max_length)
+    }
+
+    /// Provide a new COBS/R codec with a specific delimiter that scans up to a limited
+    /// number of bytes in total.
+    pub fn new_reduced_with_delimiter(delimiter: u8, max_length: usize) -> Self {
+        Self {
+            delimiter,
+            max_length,
+            is_discarding: false,
+            reduced: true,
+        }
+    }
+
+    /// Returns the worst-case number of bytes that encoding `input_len` bytes can
+    /// produce: one overhead byte per (at most) 254-byte group, plus the trailing
+    /// delimiter. Callers that want to pre-allocate an exact buffer (e.g. a fixed-size
+    /// array on a no-std/embedded path) can rely on this never being exceeded.
+    pub const fn max_encoded_len(input_len: usize) -> usize {
+        let groups = (input_len + CHUNK_LEN - 1) / CHUNK_LEN;
+        input_len + groups + 1
+    }
+
+    /// Returns an upper bound on the number of bytes a decoded frame of `encoded_len`
+    /// bytes (including its trailing delimiter) can expand to. The trailing delimiter
+    /// is never data, so the decoded length can never reach the encoded length itself
+    /// (under COBS/R the final code byte can itself be a decoded data byte).
+    pub const fn max_decoded_len(encoded_len: usize) -> usize {
+        if encoded_len == 0 {
+            0
+        } else {
+            encoded_len - 1
         }
     }
 }
 
 const CHUNK_LEN: usize = 254;
-const MAX_BYTE_OVERHEAD: usize = 2;
 
 impl Decoder for CobsCodec {
     type Item = BytesMut;
     type Error = CobsCodecError;
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        todo!()
+        loop {
+            if self.is_discarding {
+                match src.iter().position(|b| *b == self.delimiter) {
+                    Some(i) => {
+                        src.advance(i + 1);
+                        self.is_discarding = false;
+                    }
+                    None => {
+                        src.clear();
+                        return Ok(None);
+                    }
+                }
+            }
+
+            return match src.iter().position(|b| *b == self.delimiter) {
+                Some(i) => {
+                    let frame = src.split_to(i + 1);
+                    let block = Self::decode_block(&frame[..i], self.delimiter, self.reduced)?;
+                    Ok(Some(block))
+                }
+                None if src.len() > self.max_length => {
+                    self.is_discarding = true;
+                    src.clear();
+                    Err(CobsCodecError::MaxLengthExceeded)
+                }
+                None => Ok(None),
+            };
+        }
+    }
+}
+
+impl CobsCodec {
+    // Decode a single COBS block (a delimiter-terminated frame, with the trailing
+    // delimiter already stripped) into its original bytes.
+    fn decode_block(block: &[u8], delimiter: u8, reduced: bool) -> Result<BytesMut, CobsCodecError> {
+        let mut dst = BytesMut::with_capacity(block.len());
+        decode(block, &mut dst, delimiter, reduced)?;
+        Ok(dst)
     }
 }
 
@@ -58,33 +138,200 @@ impl Encoder<Bytes> for CobsCodec {
     type Error = CobsCodecError;
 
     fn encode(&mut self, src: Bytes, dst: &mut BytesMut) -> Result<(), Self::Error> {
-        let encoded_len = (src.len() / CHUNK_LEN) * (CHUNK_LEN + MAX_BYTE_OVERHEAD);
-        let encoded_remaining_len = src.len() % CHUNK_LEN;
-        let encoded_len = if encoded_remaining_len > 0 {
-            encoded_len + encoded_remaining_len + MAX_BYTE_OVERHEAD
-        } else {
-            encoded_len
-        };
-        dst.reserve(encoded_len);
-        for (i, byte) in src.iter().enumerate() {
-            if i % CHUNK_LEN == 0 {
-                dst.put_u8(0);
+        dst.reserve(Self::max_encoded_len(src.len()));
+        encode(&src, dst, self.delimiter, self.reduced);
+        Ok(())
+    }
+}
+
+/// Encode `src` as a single COBS (or, if `reduced` is set, COBS/R) frame, terminated by
+/// `delimiter`, appending the result to `dst`. This is the allocating, buffer-to-buffer
+/// building block that [`CobsCodec`]'s [`Encoder`] impl delegates to; use it directly when
+/// you have a whole payload in hand and don't need a stateful, streaming codec.
+pub fn encode(src: &[u8], dst: &mut BytesMut, delimiter: u8, reduced: bool) {
+    // `dst` may already hold previously-encoded frames (this is exactly how a
+    // `FramedWrite` drives `Encoder`, accumulating frames until a flush), so every pass
+    // below must be bounded to the frame we're building — `dst[start..]` — and never touch
+    // bytes written by an earlier call.
+    let start = dst.len();
+    // Build the frame using 0 as the sentinel, regardless of `delimiter`: the classic
+    // COBS construction below guarantees the result is zero-free except for the trailing
+    // terminator, by consuming every literal zero (real data bytes and forced markers
+    // alike) into a non-zero distance code. Only at the end do we remap 0 <-> `delimiter`
+    // across the whole frame, which turns that guaranteed-unique zero into the real
+    // terminator and turns any body byte that happened to equal `delimiter` into a zero,
+    // so the chosen delimiter can no longer collide with anything but the terminator.
+    let mut run = 0;
+    for (i, byte) in src.iter().enumerate() {
+        if i == 0 || run == CHUNK_LEN {
+            dst.put_u8(0);
+            run = 0;
+        }
+        dst.put_u8(*byte);
+        run = if *byte == 0 { 0 } else { run + 1 };
+    }
+    dst.put_u8(0);
+    let mut distance = 0;
+    for byte in dst[start..].iter_mut().rev() {
+        if *byte == 0 {
+            if distance > 0 {
+                *byte = distance;
             }
-            dst.put_u8(*byte);
+            distance = 1;
+        } else {
+            distance += 1;
+        }
+    }
+    if reduced {
+        reduce_final_group(dst, start, 0);
+    }
+    if delimiter != 0 {
+        for byte in dst[start..].iter_mut() {
+            *byte = swap_sentinel(*byte, delimiter);
         }
-        dst.put_u8(0);
-        let mut distance = 0;
-        for byte in dst.iter_mut().rev() {
-            if *byte == self.delimiter {
-                if distance > 0 {
-                    *byte = distance;
+    }
+}
+
+// Apply the COBS/R finalization to a just-encoded frame (still in 0-sentinel space):
+// given the offset `start` where that frame begins in `dst` (it may follow other,
+// already-finalized frames), if the final group's last data byte is at least as large
+// as that group's length code, fold the data byte into the code position and drop it,
+// saving the trailing overhead byte.
+fn reduce_final_group(dst: &mut BytesMut, start: usize, delimiter: u8) {
+    if dst.len() - start < 2 {
+        // Nothing to reduce: an empty frame is just its terminator.
+        return;
+    }
+    let end = dst.len() - 1;
+    let mut code_pos = start;
+    loop {
+        let code = dst[code_pos] as usize;
+        let next = code_pos + code;
+        if next == end {
+            break;
+        }
+        code_pos = next;
+    }
+    let code = dst[code_pos];
+    if code < 2 {
+        return;
+    }
+    let last_data_pos = end - 1;
+    let last_data_byte = dst[last_data_pos];
+    if last_data_byte >= code {
+        dst[code_pos] = last_data_byte;
+        dst.truncate(last_data_pos);
+        dst.put_u8(delimiter);
+    }
+}
+
+// Swap the sentinel and delimiter byte values, leaving everything else unchanged. This is
+// its own inverse, so the same function both hides `delimiter` from the encoded body and,
+// on decode, recovers the original 0-sentinel-space bytes from the wire bytes.
+fn swap_sentinel(byte: u8, delimiter: u8) -> u8 {
+    if byte == 0 {
+        delimiter
+    } else if byte == delimiter {
+        0
+    } else {
+        byte
+    }
+}
+
+/// Decode a single COBS (or, if `reduced` is set, COBS/R) frame from `src` — with any
+/// trailing delimiter already stripped — appending the decoded bytes to `dst` and
+/// returning how many were written. This is the allocating counterpart to
+/// [`decode_iter`]; [`CobsCodec`]'s [`Decoder`] impl delegates to it.
+pub fn decode(
+    src: &[u8],
+    dst: &mut BytesMut,
+    delimiter: u8,
+    reduced: bool,
+) -> Result<usize, CobsCodecError> {
+    let start = dst.len();
+    for byte in decode_iter(src, delimiter, reduced) {
+        dst.put_u8(byte?);
+    }
+    Ok(dst.len() - start)
+}
+
+/// Decode a single COBS (or COBS/R) frame from `src` — with any trailing delimiter
+/// already stripped — one byte at a time, without allocating. This is the "small,
+/// no-alloc" counterpart to [`decode`], suited to fixed-buffer or embedded-style callers.
+pub fn decode_iter(src: &[u8], delimiter: u8, reduced: bool) -> DecodeIter<'_> {
+    DecodeIter {
+        block: src,
+        idx: 0,
+        delimiter,
+        reduced,
+        group_remaining: 0,
+        pending_byte: None,
+        done: false,
+        errored: false,
+    }
+}
+
+/// An iterator over the bytes of a single decoded COBS (or COBS/R) frame. See [`decode_iter`].
+pub struct DecodeIter<'a> {
+    block: &'a [u8],
+    idx: usize,
+    delimiter: u8,
+    reduced: bool,
+    group_remaining: usize,
+    pending_byte: Option<u8>,
+    done: bool,
+    errored: bool,
+}
+
+impl<'a> Iterator for DecodeIter<'a> {
+    type Item = Result<u8, CobsCodecError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.done || self.errored {
+                return None;
+            }
+            if self.group_remaining > 0 {
+                let byte = swap_sentinel(self.block[self.idx], self.delimiter);
+                self.idx += 1;
+                self.group_remaining -= 1;
+                return Some(Ok(byte));
+            }
+            if let Some(byte) = self.pending_byte.take() {
+                return Some(Ok(byte));
+            }
+            if self.idx >= self.block.len() {
+                self.done = true;
+                return None;
+            }
+
+            let code = swap_sentinel(self.block[self.idx], self.delimiter) as usize;
+            if code == 0 {
+                self.errored = true;
+                return Some(Err(CobsCodecError::CorruptPacket));
+            }
+            self.idx += 1;
+            let remaining = self.block.len() - self.idx;
+            if code - 1 > remaining {
+                if !self.reduced {
+                    self.errored = true;
+                    return Some(Err(CobsCodecError::CorruptPacket));
                 }
-                distance = 1;
-            } else {
-                distance += 1;
+                // COBS/R: the final group's data is whatever is left, and the code
+                // byte itself (already un-mapped) is the last decoded byte.
+                self.group_remaining = remaining;
+                self.pending_byte = Some(code as u8);
+                continue;
+            }
+            self.group_remaining = code - 1;
+            let group_end = self.idx + self.group_remaining;
+            if code != 0xFF && group_end < self.block.len() {
+                // A code byte that isn't the final one always stood in for a literal
+                // zero data byte in the original input; reinsert it directly (not
+                // `self.delimiter` — that's only the wire framing byte).
+                self.pending_byte = Some(0);
             }
         }
-        Ok(())
     }
 }
 
@@ -93,6 +340,10 @@ impl Encoder<Bytes> for CobsCodec {
 pub enum CobsCodecError {
     /// The maximum length was exceeded.
     MaxLengthExceeded,
+    /// A frame could not be decoded because it was not valid COBS: either a zero code
+    /// byte appeared inside the frame, or a code byte pointed past the bytes available
+    /// before the delimiter.
+    CorruptPacket,
     /// An IO error occurred.
     Io(io::Error),
 }
@@ -101,6 +352,7 @@ impl fmt::Display for CobsCodecError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             CobsCodecError::MaxLengthExceeded => write!(f, "max length exceeded"),
+            CobsCodecError::CorruptPacket => write!(f, "corrupt COBS packet"),
             CobsCodecError::Io(e) => write!(f, "{}", e),
         }
     }
@@ -113,3 +365,142 @@ impl From<io::Error> for CobsCodecError {
 }
 
 impl std::error::Error for CobsCodecError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(codec: &mut CobsCodec, data: &[u8]) -> BytesMut {
+        let mut dst = BytesMut::new();
+        codec
+            .encode(Bytes::copy_from_slice(data), &mut dst)
+            .unwrap();
+        let encoded = dst.clone();
+        let decoded = codec.decode(&mut dst).unwrap().unwrap();
+        assert_eq!(&decoded[..], data);
+        encoded
+    }
+
+    #[test]
+    fn reduced_roundtrips_like_plain_cobs() {
+        let mut codec = CobsCodec::new_reduced(1024);
+        roundtrip(&mut codec, b"");
+        roundtrip(&mut codec, b"hello, world");
+        roundtrip(&mut codec, &[0, 1, 0, 2, 0]);
+    }
+
+    #[test]
+    fn reduced_saves_the_trailing_overhead_byte() {
+        let mut plain = CobsCodec::new(1024);
+        let mut reduced = CobsCodec::new_reduced(1024);
+        // Last byte (250) is >= the final group's length code (5: 1 code byte + 4 data
+        // bytes), so COBS/R elides it.
+        let data = [1u8, 2, 3, 250];
+
+        let plain_encoded = roundtrip(&mut plain, &data);
+        let reduced_encoded = roundtrip(&mut reduced, &data);
+
+        assert_eq!(reduced_encoded.len(), plain_encoded.len() - 1);
+    }
+
+    #[test]
+    fn non_zero_delimiter_never_appears_in_the_payload() {
+        let mut codec = CobsCodec::new_with_delimiter(b'\n', 1024);
+        let data = [b'\n'; 16];
+        let encoded = roundtrip(&mut codec, &data);
+
+        let delimiter_positions: Vec<usize> = encoded
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| **b == b'\n')
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(delimiter_positions, vec![encoded.len() - 1]);
+    }
+
+    #[test]
+    fn non_zero_delimiter_survives_code_byte_collision() {
+        // Chosen so the group's distance code (3) collides with the delimiter (3); the
+        // 16-byte all-`\n` payload above doesn't exercise this, since its only code is 17.
+        let mut codec = CobsCodec::new_with_delimiter(3, 1024);
+        let data = [0x11u8, 0x22];
+        let encoded = roundtrip(&mut codec, &data);
+
+        let delimiter_positions: Vec<usize> = encoded
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| **b == 3)
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(delimiter_positions, vec![encoded.len() - 1]);
+    }
+
+    #[test]
+    fn non_zero_delimiter_roundtrips_with_mixed_data() {
+        let mut codec = CobsCodec::new_with_delimiter(b'\n', 1024);
+        roundtrip(&mut codec, b"");
+        roundtrip(&mut codec, b"no newlines here");
+        roundtrip(&mut codec, b"a\nb\nc\n\n\nd");
+        // Real NUL bytes alongside the chosen delimiter exercise both the group-boundary
+        // reinsertion path and the delimiter/sentinel swap in the same frame.
+        roundtrip(&mut codec, &[0, b'\n', 0, b'\n']);
+    }
+
+    #[test]
+    fn reduced_boundary_where_final_byte_equals_group_length() {
+        // Final byte (5) equals the group's length code (5) exactly: still eligible for
+        // reduction, since the rule is "final byte >= code".
+        let mut codec = CobsCodec::new_reduced(1024);
+        let data = [1u8, 2, 3, 5];
+        let encoded = roundtrip(&mut codec, &data);
+
+        let mut plain = CobsCodec::new(1024);
+        let plain_encoded = roundtrip(&mut plain, &data);
+        assert_eq!(encoded.len(), plain_encoded.len() - 1);
+    }
+
+    #[test]
+    fn free_functions_roundtrip_without_a_codec() {
+        let data = b"no stateful codec needed";
+        let mut encoded = BytesMut::new();
+        encode(data, &mut encoded, 0, false);
+
+        let block = &encoded[..encoded.len() - 1];
+        let mut decoded = BytesMut::new();
+        let n = decode(block, &mut decoded, 0, false).unwrap();
+
+        assert_eq!(n, data.len());
+        assert_eq!(&decoded[..], &data[..]);
+    }
+
+    #[test]
+    fn decode_iter_yields_bytes_without_allocating() {
+        let data = b"byte by byte";
+        let mut encoded = BytesMut::new();
+        encode(data, &mut encoded, 0, false);
+
+        let block = &encoded[..encoded.len() - 1];
+        let decoded: Result<Vec<u8>, _> = decode_iter(block, 0, false).collect();
+        assert_eq!(decoded.unwrap(), data);
+    }
+
+    #[test]
+    fn encoding_twice_into_one_buffer_does_not_corrupt_the_first_frame() {
+        // This is exactly how `FramedWrite` drives `Encoder`: frames accumulate in one
+        // buffer until a flush, so a second `encode` call must not rewrite bytes an
+        // earlier call already wrote.
+        let mut codec = CobsCodec::new(1024);
+        let mut buf = BytesMut::new();
+        codec
+            .encode(Bytes::copy_from_slice(b"first"), &mut buf)
+            .unwrap();
+        codec
+            .encode(Bytes::copy_from_slice(b"second"), &mut buf)
+            .unwrap();
+
+        let first = codec.decode(&mut buf).unwrap().unwrap();
+        let second = codec.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(&first[..], b"first");
+        assert_eq!(&second[..], b"second");
+    }
+}